@@ -9,60 +9,402 @@ casper-types = "1.4.6"
 extern crate alloc;
 
 // Importing Rust types.
+use alloc::boxed::Box;
 use alloc::string::{String, ToString};
 use alloc::vec;
+use alloc::vec::Vec;
 // Importing aspects of the Casper platform.
-use casper_contract::contract_api::storage::dictionary_get;
 use casper_contract::contract_api::{runtime, storage, system};
 use casper_contract::unwrap_or_revert::UnwrapOrRevert;
 // Importing specific Casper types.
 use casper_types::account::AccountHash;
+use casper_types::bytesrepr::{self, FromBytes, ToBytes};
 use casper_types::contracts::NamedKeys;
-use casper_types::{runtime_args, CLType, CLValue, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints, Key, Parameter, ApiError, RuntimeArgs};
-
+use casper_types::{
+    runtime_args, ApiError, BlockTime, CLType, CLTyped, CLValue, ContractHash, EntryPoint,
+    EntryPointAccess, EntryPointType, EntryPoints, Key, Parameter, RuntimeArgs, URef, U256, U512,
+};
+use casper_types::system::CallStackElement;
 
 // Creating constants for the various contract entry points.
 const ENTRY_POINT_INIT: &str = "init";
 const ENTRY_POINT_DONATE: &str = "donate";
 const ENTRY_POINT_GET_DONATION_COUNT: &str = "get_donation_count";
 const ENTRY_POINT_GET_FUNDS_RAISED: &str = "get_funds_raised";
+const ENTRY_POINT_FINALIZE: &str = "finalize";
+const ENTRY_POINT_REFUND: &str = "refund";
+const ENTRY_POINT_FINALIZE_TOKEN: &str = "finalize_token";
+const ENTRY_POINT_REFUND_TOKEN: &str = "refund_token";
+const ENTRY_POINT_MIGRATE: &str = "migrate";
+const ENTRY_POINT_SET_ADMIN: &str = "set_admin";
+const ENTRY_POINT_ADD_TO_BLACKLIST: &str = "add_to_blacklist";
+const ENTRY_POINT_REMOVE_FROM_BLACKLIST: &str = "remove_from_blacklist";
+const ENTRY_POINT_DONATE_TOKEN: &str = "donate_token";
+const ENTRY_POINT_GET_TOKEN_FUNDS_RAISED: &str = "get_token_funds_raised";
+const ENTRY_POINT_GET_RECEIPT: &str = "get_receipt";
+const ENTRY_POINT_EXPORT_NAMED_KEYS: &str = "export_named_keys";
 
-// Creating constants for values within the contract.
+// Creating constants for named arguments accepted by entry points.
 const DONATING_ACCOUNT_KEY: &str = "donating_account_key";
+const DONATION_PURSE_ARG: &str = "donation_purse";
+const AMOUNT_ARG: &str = "amount";
+const AMOUNT_TO_RAISE_ARG: &str = "amount_to_raise";
+const DURATION_ARG: &str = "duration";
+const MAKER_ARG: &str = "maker";
+const LOCK_PACKAGE_ARG: &str = "lock_package";
+const PREVIOUS_NAMED_KEYS_ARG: &str = "previous_named_keys";
+const ADMIN_ARG: &str = "admin";
+const ACCOUNT_ARG: &str = "account";
+const TOKEN_CONTRACT_HASH_ARG: &str = "token_contract_hash";
+const TOKEN_TRANSFER_FROM_ENTRY_POINT: &str = "transfer_from";
+const TOKEN_TRANSFER_ENTRY_POINT: &str = "transfer";
+const TOKEN_BALANCE_OF_ENTRY_POINT: &str = "balance_of";
+const TOKEN_OWNER_ARG: &str = "owner";
+const TOKEN_RECIPIENT_ARG: &str = "recipient";
+const TOKEN_ADDRESS_ARG: &str = "address";
+
+// Creating constants for the named keys that back the contract's persistent state.
 const LEDGER: &str = "ledger";
+const DONOR_ACCOUNTS: &str = "donor_accounts";
 const FUNDRAISING_PURSE: &str = "fundraising_purse";
+const AMOUNT_TO_RAISE_KEY: &str = "amount_to_raise";
+const TIME_STARTED_KEY: &str = "time_started";
+const DURATION_KEY: &str = "duration";
+const MAKER_KEY: &str = "maker";
+const FINALIZED_KEY: &str = "finalized";
+const ADMIN_KEY: &str = "admin";
+const BLACKLIST: &str = "blacklist";
+const ACCEPTED_TOKEN_KEY: &str = "accepted_token";
+const EVENTS: &str = "events";
+const EVENT_COUNT_KEY: &str = "event_count";
+const EVENTS_SCHEMA_KEY: &str = "__events_schema";
+const EVENTS_LENGTH_KEY: &str = "__events_length";
+const CONTRACT_VERSION_KEY: &str = "contract_version";
+const FUNDRAISER_PACKAGE_HASH_KEY: &str = "fundraiser_package_hash";
+const FUNDRAISER_ACCESS_UREF_KEY: &str = "fundraiser_access_uref";
+const CONTRACT_HASH_KEY: &str = "fundraiser_contract_hash";
+
+// Bump whenever the on-chain layout of the `ledger` dictionary's records changes.
+// `migrate` compares this against the stored `contract_version` to decide whether a
+// freshly-upgraded contract still needs to rewrite its ledger entries.
+const CURRENT_CONTRACT_VERSION: u32 = 3;
 
+// Errors specific to the fundraising contract, surfaced to callers as `ApiError::User(n)`.
+#[repr(u16)]
+pub enum FundRaisingError {
+    InvalidKeyVariant = 0,
+    MissingFundRaisingPurseURef = 1,
+    MissingLedgerSeedURef = 2,
+    MissingNamedKey = 3,
+    GoalNotMet = 4,
+    DeadlinePassed = 5,
+    GoalAlreadyMet = 6,
+    Unauthorized = 7,
+    BlacklistedAccount = 8,
+    WrongDonationMode = 9,
+}
 
-// This entry point initializes the donation system, setting up the fundraising purse
-// and creating a dictionary to track the account hashes and the number of donations
-// made.
+impl From<FundRaisingError> for ApiError {
+    fn from(error: FundRaisingError) -> ApiError {
+        ApiError::User(error as u16)
+    }
+}
+
+// A donor's standing with the fundraiser: how many times they've donated, how much in
+// total they've contributed so far, and when they last donated.
+#[derive(Clone, Copy)]
+pub struct LedgerRecord {
+    pub donation_count: u64,
+    pub total_donated: U512,
+    pub last_block_time: BlockTime,
+}
+
+impl CLTyped for LedgerRecord {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+impl ToBytes for LedgerRecord {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.donation_count.to_bytes()?);
+        buffer.extend(self.total_donated.to_bytes()?);
+        buffer.extend(self.last_block_time.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.donation_count.serialized_length()
+            + self.total_donated.serialized_length()
+            + self.last_block_time.serialized_length()
+    }
+}
+
+impl FromBytes for LedgerRecord {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (donation_count, remainder) = u64::from_bytes(bytes)?;
+        let (total_donated, remainder) = U512::from_bytes(remainder)?;
+        let (last_block_time, remainder) = BlockTime::from_bytes(remainder)?;
+        Ok((
+            LedgerRecord {
+                donation_count,
+                total_donated,
+                last_block_time,
+            },
+            remainder,
+        ))
+    }
+}
+
+// The events this contract emits, following a CES-style (Casper Event Standard) schema so
+// off-chain indexers can discover the event shapes up front and then replay the full
+// history from the `events` dictionary.
+pub enum FundRaisingEvent {
+    Donation {
+        donor: Key,
+        amount: U512,
+        new_total: U512,
+    },
+    GoalReached {
+        total: U512,
+    },
+    Refund {
+        donor: Key,
+        amount: U512,
+    },
+}
+
+impl CLTyped for FundRaisingEvent {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+impl ToBytes for FundRaisingEvent {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        match self {
+            FundRaisingEvent::Donation {
+                donor,
+                amount,
+                new_total,
+            } => {
+                buffer.push(0u8);
+                buffer.extend(donor.to_bytes()?);
+                buffer.extend(amount.to_bytes()?);
+                buffer.extend(new_total.to_bytes()?);
+            }
+            FundRaisingEvent::GoalReached { total } => {
+                buffer.push(1u8);
+                buffer.extend(total.to_bytes()?);
+            }
+            FundRaisingEvent::Refund { donor, amount } => {
+                buffer.push(2u8);
+                buffer.extend(donor.to_bytes()?);
+                buffer.extend(amount.to_bytes()?);
+            }
+        }
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        1 + match self {
+            FundRaisingEvent::Donation {
+                donor,
+                amount,
+                new_total,
+            } => {
+                donor.serialized_length()
+                    + amount.serialized_length()
+                    + new_total.serialized_length()
+            }
+            FundRaisingEvent::GoalReached { total } => total.serialized_length(),
+            FundRaisingEvent::Refund { donor, amount } => {
+                donor.serialized_length() + amount.serialized_length()
+            }
+        }
+    }
+}
+
+impl FromBytes for FundRaisingEvent {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (tag, remainder) = u8::from_bytes(bytes)?;
+        match tag {
+            0 => {
+                let (donor, remainder) = Key::from_bytes(remainder)?;
+                let (amount, remainder) = U512::from_bytes(remainder)?;
+                let (new_total, remainder) = U512::from_bytes(remainder)?;
+                Ok((
+                    FundRaisingEvent::Donation {
+                        donor,
+                        amount,
+                        new_total,
+                    },
+                    remainder,
+                ))
+            }
+            1 => {
+                let (total, remainder) = U512::from_bytes(remainder)?;
+                Ok((FundRaisingEvent::GoalReached { total }, remainder))
+            }
+            2 => {
+                let (donor, remainder) = Key::from_bytes(remainder)?;
+                let (amount, remainder) = U512::from_bytes(remainder)?;
+                Ok((FundRaisingEvent::Refund { donor, amount }, remainder))
+            }
+            _ => Err(bytesrepr::Error::Formatting),
+        }
+    }
+}
+
+// The schema describing every event this contract can emit: each entry names an event
+// variant and lists its fields' names and CLTypes, so that indexers can decode the raw
+// bytes written to the `events` dictionary without having this contract's source.
+fn event_schemas() -> Vec<(String, Vec<(String, CLType)>)> {
+    vec![
+        (
+            "Donation".to_string(),
+            vec![
+                ("donor".to_string(), CLType::Key),
+                ("amount".to_string(), CLType::U512),
+                ("new_total".to_string(), CLType::U512),
+            ],
+        ),
+        (
+            "GoalReached".to_string(),
+            vec![("total".to_string(), CLType::U512)],
+        ),
+        (
+            "Refund".to_string(),
+            vec![
+                ("donor".to_string(), CLType::Key),
+                ("amount".to_string(), CLType::U512),
+            ],
+        ),
+    ]
+}
+
+// Appends `event` to the `events` dictionary under the next incrementing index, and
+// advances the shared event-count uref (exposed to off-chain tooling as `__events_length`).
+fn emit_event(event: FundRaisingEvent) {
+    let events_seed_uref = get_uref(EVENTS, FundRaisingError::MissingNamedKey);
+    let event_count_uref = get_uref(EVENT_COUNT_KEY, FundRaisingError::MissingNamedKey);
+
+    let index: u64 = storage::read(event_count_uref)
+        .unwrap_or_revert()
+        .unwrap_or_revert();
+    storage::dictionary_put(events_seed_uref, &index.to_string(), event);
+    storage::write(event_count_uref, index + 1);
+}
+
+// This entry point initializes the donation system, setting up the fundraising purse,
+// recording the fundraising goal/deadline/beneficiary, and creating a dictionary to
+// track each donor's contributions.
 #[no_mangle]
 pub extern "C" fn init() {
+    let amount_to_raise: U512 = runtime::get_named_arg(AMOUNT_TO_RAISE_ARG);
+    let duration: u64 = runtime::get_named_arg(DURATION_ARG);
+    let maker: Key = runtime::get_named_arg(MAKER_ARG);
+    let token_contract_hash: Option<ContractHash> = runtime::get_named_arg(TOKEN_CONTRACT_HASH_ARG);
+
     let fundraising_purse = system::create_purse();
     runtime::put_key(FUNDRAISING_PURSE, fundraising_purse.into());
-    // Create a dictionary to track the mapping of account hashes to number of donations made.
+
+    runtime::put_key(
+        AMOUNT_TO_RAISE_KEY,
+        storage::new_uref(amount_to_raise).into(),
+    );
+    // `accepted_token` fixes this fundraiser's donation mode for its lifetime: `None`
+    // accepts native CSPR through `donate`, `Some(hash)` accepts that CEP-18 token
+    // through `donate_token`. The two modes are mutually exclusive.
+    runtime::put_key(
+        ACCEPTED_TOKEN_KEY,
+        storage::new_uref(token_contract_hash).into(),
+    );
+    runtime::put_key(
+        TIME_STARTED_KEY,
+        storage::new_uref(runtime::get_blocktime()).into(),
+    );
+    runtime::put_key(DURATION_KEY, storage::new_uref(duration).into());
+    runtime::put_key(MAKER_KEY, storage::new_uref(maker).into());
+    runtime::put_key(FINALIZED_KEY, storage::new_uref(false).into());
+
+    // A freshly installed contract starts out on the current ledger layout, so
+    // `migrate` is a no-op until an older deployment is upgraded onto this code.
+    runtime::put_key(
+        CONTRACT_VERSION_KEY,
+        storage::new_uref(CURRENT_CONTRACT_VERSION).into(),
+    );
+
+    // The installing account is the initial admin, able to hand off the role or manage
+    // the donor blacklist.
+    runtime::put_key(
+        ADMIN_KEY,
+        storage::new_uref(Key::Account(runtime::get_caller())).into(),
+    );
+    storage::new_dictionary(BLACKLIST).unwrap_or_revert();
+
+    // Create a dictionary to track the mapping of account hashes to each donor's ledger
+    // record, and a named key holding the list of donor accounts so that a `refund` can
+    // later walk every donor without requiring dictionary enumeration.
     storage::new_dictionary(LEDGER).unwrap_or_revert();
+    runtime::put_key(
+        DONOR_ACCOUNTS,
+        storage::new_uref(Vec::<AccountHash>::new()).into(),
+    );
+
+    // Create the dictionary that holds the serialized event log, and alias `event_count`
+    // to the `__events_length` uref that was registered as a named key at contract
+    // creation, so `emit_event` keeps both in lock-step.
+    storage::new_dictionary(EVENTS).unwrap_or_revert();
+    runtime::put_key(
+        EVENT_COUNT_KEY,
+        get_uref(EVENTS_LENGTH_KEY, FundRaisingError::MissingNamedKey).into(),
+    );
+
+    register_self_contract_keys();
 }
 
-// This is the donation entry point. When called, it records the caller's account
-// hash and returns the donation purse, with add access, to the immediate caller.
+// This is the donation entry point. The caller donates `amount` out of `donation_purse`
+// into the fundraising purse, and the donation is recorded against the donor's ledger
+// record. A receipt of the donor's updated standing, `(donation_count, total_donated,
+// last_block_time, contract_hash)`, is returned to the immediate caller so that calling
+// contracts may store proof of their contribution.
 #[no_mangle]
 pub extern "C" fn donate() {
+    assert_accepted_token(None);
+    assert_fundraiser_open();
+
     let donating_account_key: Key = runtime::get_named_arg(DONATING_ACCOUNT_KEY);
-    if let Key::Account(donating_account_hash) = donating_account_key {
-        update_ledger_record(donating_account_hash.to_string())
+    let donation_purse: URef = runtime::get_named_arg(DONATION_PURSE_ARG);
+    let amount: U512 = runtime::get_named_arg(AMOUNT_ARG);
+
+    let donating_account_hash = if let Key::Account(donating_account_hash) = donating_account_key
+    {
+        donating_account_hash
     } else {
         runtime::revert(FundRaisingError::InvalidKeyVariant)
-    }
-    let donation_purse = *runtime::get_key(FUNDRAISING_PURSE)
-        .unwrap_or_revert_with(FundRaisingError::MissingFundRaisingPurseURef)
-        .as_uref()
+    };
+
+    reject_if_blacklisted(donating_account_hash);
+
+    let fundraising_purse = get_uref(FUNDRAISING_PURSE, FundRaisingError::MissingFundRaisingPurseURef);
+
+    system::transfer_from_purse_to_purse(donation_purse, fundraising_purse, amount, None)
         .unwrap_or_revert();
-    // The return value is the donation_purse URef with `add` access only. As a result
-    // the entity receiving this purse URef may only add to the purse, and cannot remove
-    // funds.
-    let value = CLValue::from_t(donation_purse.into_add()).unwrap_or_revert();
-    runtime::ret(value)
+
+    let record = update_ledger_record(donating_account_hash, amount);
+    emit_event(FundRaisingEvent::Donation {
+        donor: donating_account_key,
+        amount,
+        new_total: record.total_donated,
+    });
+
+    // Return a verifiable receipt of this donation so a calling contract can record
+    // proof of its contribution in its own state.
+    let receipt = build_receipt(record);
+    runtime::ret(CLValue::from_t(receipt).unwrap_or_revert())
 }
 
 // This entry point returns the amount of donations from the caller.
@@ -70,34 +412,631 @@ pub extern "C" fn donate() {
 pub extern "C" fn get_donation_count() {
     let donating_account_key: Key = runtime::get_named_arg(DONATING_ACCOUNT_KEY);
     if let Key::Account(donating_account_hash) = donating_account_key {
-        let ledger_seed_uref = *runtime::get_key(LEDGER)
-            .unwrap_or_revert_with(FundRaisingError::MissingLedgerSeedURef)
-            .as_uref()
-            .unwrap_or_revert();
-        let donation_count = if let Some(donation_count) =
-            storage::dictionary_get::<u64>(ledger_seed_uref, &donating_account_hash.to_string())
-                .unwrap_or_revert()
-        {
-            donation_count
-        } else {
-            0u64
-        };
+        let donation_count = read_ledger_record(donating_account_hash)
+            .map(|record| record.donation_count)
+            .unwrap_or(0u64);
         runtime::ret(CLValue::from_t(donation_count).unwrap_or_revert())
     } else {
         runtime::revert(FundRaisingError::InvalidKeyVariant)
     }
 }
 
+// This entry point returns a verifiable receipt of `donating_account_key`'s standing
+// with the fundraiser: `(donation_count, total_donated, last_block_time, contract_hash)`.
+// A donor who has never donated gets a zeroed receipt rather than a revert.
+#[no_mangle]
+pub extern "C" fn get_receipt() {
+    let donating_account_key: Key = runtime::get_named_arg(DONATING_ACCOUNT_KEY);
+    let donating_account_hash = if let Key::Account(donating_account_hash) = donating_account_key
+    {
+        donating_account_hash
+    } else {
+        runtime::revert(FundRaisingError::InvalidKeyVariant)
+    };
+
+    let record = read_ledger_record(donating_account_hash).unwrap_or(LedgerRecord {
+        donation_count: 0,
+        total_donated: U512::zero(),
+        last_block_time: BlockTime::new(0),
+    });
+
+    let receipt = build_receipt(record);
+    runtime::ret(CLValue::from_t(receipt).unwrap_or_revert())
+}
+
+// This entry point returns this contract's own named keys. `call()`'s upgrade branch
+// calls it on the previous contract version before publishing a new one, so the new
+// version's named keys can be seeded with the old version's state (fundraising purse,
+// ledger, donor list, admin, blacklist, event log, …) instead of starting from an empty
+// table, which would orphan all of it.
+#[no_mangle]
+pub extern "C" fn export_named_keys() {
+    runtime::ret(CLValue::from_t(runtime::list_named_keys()).unwrap_or_revert())
+}
+
 // This entry point returns the total funds raised.
 #[no_mangle]
 pub extern "C" fn get_funds_raised() {
-    let donation_purse = *runtime::get_key(FUNDRAISING_PURSE)
-        .unwrap_or_revert_with(FundRaisingError::MissingFundRaisingPurseURef)
-        .as_uref()
+    let fundraising_purse = get_uref(FUNDRAISING_PURSE, FundRaisingError::MissingFundRaisingPurseURef);
+    let funds_raised = system::get_purse_balance(fundraising_purse).unwrap_or_revert();
+    runtime::ret(CLValue::from_t(funds_raised).unwrap_or_revert())
+}
+
+// This is the CEP-18 counterpart to `donate`, for fundraisers set up to accept a
+// specific token instead of native CSPR. The donor's tokens move directly from their
+// own balance into this contract's via the token contract's `transfer_from`.
+#[no_mangle]
+pub extern "C" fn donate_token() {
+    let token_contract_hash = get_accepted_token()
+        .unwrap_or_revert_with(FundRaisingError::WrongDonationMode);
+    assert_fundraiser_open();
+
+    let donating_account_key: Key = runtime::get_named_arg(DONATING_ACCOUNT_KEY);
+    let amount: U256 = runtime::get_named_arg(AMOUNT_ARG);
+
+    let donating_account_hash = if let Key::Account(donating_account_hash) = donating_account_key
+    {
+        donating_account_hash
+    } else {
+        runtime::revert(FundRaisingError::InvalidKeyVariant)
+    };
+
+    reject_if_blacklisted(donating_account_hash);
+
+    runtime::call_contract::<()>(
+        token_contract_hash,
+        TOKEN_TRANSFER_FROM_ENTRY_POINT,
+        runtime_args! {
+            TOKEN_OWNER_ARG => donating_account_key,
+            TOKEN_RECIPIENT_ARG => this_contract_key(),
+            AMOUNT_ARG => amount,
+        },
+    );
+
+    let amount_u512 = u256_to_u512(amount);
+    let record = update_ledger_record(donating_account_hash, amount_u512);
+    emit_event(FundRaisingEvent::Donation {
+        donor: donating_account_key,
+        amount: amount_u512,
+        new_total: record.total_donated,
+    });
+}
+
+// This entry point returns the total amount of the accepted CEP-18 token donated so
+// far, by cross-calling `balance_of` on the token contract for this contract's own key.
+#[no_mangle]
+pub extern "C" fn get_token_funds_raised() {
+    let token_contract_hash = get_accepted_token()
+        .unwrap_or_revert_with(FundRaisingError::WrongDonationMode);
+
+    let funds_raised: U256 = runtime::call_contract(
+        token_contract_hash,
+        TOKEN_BALANCE_OF_ENTRY_POINT,
+        runtime_args! {
+            TOKEN_ADDRESS_ARG => this_contract_key(),
+        },
+    );
+    runtime::ret(CLValue::from_t(funds_raised).unwrap_or_revert())
+}
+
+// This entry point finalizes a successful fundraiser: once the fundraising purse holds
+// at least `amount_to_raise`, the full balance is handed over to the beneficiary and the
+// fundraiser is marked `finalized` so it cannot be finalized or refunded again. Unlike
+// `refund`, this is not gated on the deadline: a goal that was met before the deadline
+// must still be finalizable after it, otherwise the funds would be stranded (`finalize`
+// could no longer succeed, and `refund` would still see the goal met and refuse to pay
+// anyone out). Only meaningful for a native-CSPR fundraiser: this reverts with
+// `WrongDonationMode` if the fundraiser was set up to accept a token instead, whose
+// collected tokens are settled by `finalize_token`.
+#[no_mangle]
+pub extern "C" fn finalize() {
+    assert_accepted_token(None);
+
+    let finalized_uref = get_uref(FINALIZED_KEY, FundRaisingError::MissingNamedKey);
+    let finalized: bool = storage::read(finalized_uref).unwrap_or_revert().unwrap_or_revert();
+    if finalized {
+        runtime::revert(FundRaisingError::GoalAlreadyMet)
+    }
+
+    let fundraising_purse = get_uref(FUNDRAISING_PURSE, FundRaisingError::MissingFundRaisingPurseURef);
+    let balance = system::get_purse_balance(fundraising_purse).unwrap_or_revert();
+
+    let amount_to_raise_uref = get_uref(AMOUNT_TO_RAISE_KEY, FundRaisingError::MissingNamedKey);
+    let amount_to_raise: U512 = storage::read(amount_to_raise_uref)
+        .unwrap_or_revert()
         .unwrap_or_revert();
-    let funds_raised = system::get_purse_balance(donation_purse)
+    if balance < amount_to_raise {
+        runtime::revert(FundRaisingError::GoalNotMet)
+    }
+
+    // Set `finalized` before issuing the transfer so a reentrant or repeated call to
+    // `finalize` cannot drain the purse twice.
+    storage::write(finalized_uref, true);
+
+    let maker_uref = get_uref(MAKER_KEY, FundRaisingError::MissingNamedKey);
+    let maker: Key = storage::read(maker_uref).unwrap_or_revert().unwrap_or_revert();
+    let maker_account = maker.into_account().unwrap_or_revert();
+
+    system::transfer_from_purse_to_account(fundraising_purse, maker_account, balance, None)
         .unwrap_or_revert();
-    runtime::ret(CLValue::from_t(funds_raised).unwrap_or_revert())
+
+    emit_event(FundRaisingEvent::GoalReached { total: balance });
+}
+
+// This entry point refunds every donor once the deadline has passed without the
+// fundraising goal having been met. Like `finalize`, it can only ever run once, and only
+// ever applies to a native-CSPR fundraiser: it reverts with `WrongDonationMode` if the
+// fundraiser was set up to accept a token instead, whose donors are refunded by
+// `refund_token`.
+#[no_mangle]
+pub extern "C" fn refund() {
+    assert_accepted_token(None);
+
+    let finalized_uref = get_uref(FINALIZED_KEY, FundRaisingError::MissingNamedKey);
+    let finalized: bool = storage::read(finalized_uref).unwrap_or_revert().unwrap_or_revert();
+    if finalized {
+        runtime::revert(FundRaisingError::GoalAlreadyMet)
+    }
+
+    if !deadline_has_passed() {
+        runtime::revert(FundRaisingError::DeadlinePassed)
+    }
+
+    let fundraising_purse = get_uref(FUNDRAISING_PURSE, FundRaisingError::MissingFundRaisingPurseURef);
+    let balance = system::get_purse_balance(fundraising_purse).unwrap_or_revert();
+
+    let amount_to_raise_uref = get_uref(AMOUNT_TO_RAISE_KEY, FundRaisingError::MissingNamedKey);
+    let amount_to_raise: U512 = storage::read(amount_to_raise_uref)
+        .unwrap_or_revert()
+        .unwrap_or_revert();
+    if balance >= amount_to_raise {
+        runtime::revert(FundRaisingError::GoalAlreadyMet)
+    }
+
+    // Set `finalized` before issuing any transfers so a malicious caller cannot invoke
+    // `refund` repeatedly and drain each donor's contribution more than once.
+    storage::write(finalized_uref, true);
+
+    let ledger_seed_uref = get_uref(LEDGER, FundRaisingError::MissingLedgerSeedURef);
+    let donor_accounts_uref = get_uref(DONOR_ACCOUNTS, FundRaisingError::MissingNamedKey);
+    let donor_accounts: Vec<AccountHash> = storage::read(donor_accounts_uref)
+        .unwrap_or_revert()
+        .unwrap_or_revert();
+
+    for donor in donor_accounts {
+        if let Some(record) =
+            storage::dictionary_get::<LedgerRecord>(ledger_seed_uref, &donor.to_string())
+                .unwrap_or_revert()
+        {
+            if !record.total_donated.is_zero() {
+                system::transfer_from_purse_to_account(
+                    fundraising_purse,
+                    donor,
+                    record.total_donated,
+                    None,
+                )
+                .unwrap_or_revert();
+                emit_event(FundRaisingEvent::Refund {
+                    donor: Key::Account(donor),
+                    amount: record.total_donated,
+                });
+            }
+        }
+    }
+}
+
+// This is the CEP-18 counterpart to `finalize`, for a fundraiser set up to accept a
+// token instead of native CSPR: once this contract's token balance holds at least
+// `amount_to_raise`, the full balance is transferred to the beneficiary. Like `finalize`,
+// it is not gated on the deadline and can only ever run once.
+#[no_mangle]
+pub extern "C" fn finalize_token() {
+    let token_contract_hash =
+        get_accepted_token().unwrap_or_revert_with(FundRaisingError::WrongDonationMode);
+
+    let finalized_uref = get_uref(FINALIZED_KEY, FundRaisingError::MissingNamedKey);
+    let finalized: bool = storage::read(finalized_uref).unwrap_or_revert().unwrap_or_revert();
+    if finalized {
+        runtime::revert(FundRaisingError::GoalAlreadyMet)
+    }
+
+    let balance: U256 = runtime::call_contract(
+        token_contract_hash,
+        TOKEN_BALANCE_OF_ENTRY_POINT,
+        runtime_args! {
+            TOKEN_ADDRESS_ARG => this_contract_key(),
+        },
+    );
+
+    let amount_to_raise_uref = get_uref(AMOUNT_TO_RAISE_KEY, FundRaisingError::MissingNamedKey);
+    let amount_to_raise: U512 = storage::read(amount_to_raise_uref)
+        .unwrap_or_revert()
+        .unwrap_or_revert();
+    if u256_to_u512(balance) < amount_to_raise {
+        runtime::revert(FundRaisingError::GoalNotMet)
+    }
+
+    // Set `finalized` before issuing the transfer so a reentrant or repeated call to
+    // `finalize_token` cannot drain the token balance twice.
+    storage::write(finalized_uref, true);
+
+    let maker_uref = get_uref(MAKER_KEY, FundRaisingError::MissingNamedKey);
+    let maker: Key = storage::read(maker_uref).unwrap_or_revert().unwrap_or_revert();
+
+    runtime::call_contract::<()>(
+        token_contract_hash,
+        TOKEN_TRANSFER_ENTRY_POINT,
+        runtime_args! {
+            TOKEN_RECIPIENT_ARG => maker,
+            AMOUNT_ARG => balance,
+        },
+    );
+
+    emit_event(FundRaisingEvent::GoalReached {
+        total: u256_to_u512(balance),
+    });
+}
+
+// This is the CEP-18 counterpart to `refund`, for a fundraiser set up to accept a token
+// instead of native CSPR: once the deadline has passed without the fundraising goal
+// having been met, every donor's tokens are transferred back to them. Like `refund`, it
+// can only ever run once.
+#[no_mangle]
+pub extern "C" fn refund_token() {
+    let token_contract_hash =
+        get_accepted_token().unwrap_or_revert_with(FundRaisingError::WrongDonationMode);
+
+    let finalized_uref = get_uref(FINALIZED_KEY, FundRaisingError::MissingNamedKey);
+    let finalized: bool = storage::read(finalized_uref).unwrap_or_revert().unwrap_or_revert();
+    if finalized {
+        runtime::revert(FundRaisingError::GoalAlreadyMet)
+    }
+
+    if !deadline_has_passed() {
+        runtime::revert(FundRaisingError::DeadlinePassed)
+    }
+
+    let balance: U256 = runtime::call_contract(
+        token_contract_hash,
+        TOKEN_BALANCE_OF_ENTRY_POINT,
+        runtime_args! {
+            TOKEN_ADDRESS_ARG => this_contract_key(),
+        },
+    );
+
+    let amount_to_raise_uref = get_uref(AMOUNT_TO_RAISE_KEY, FundRaisingError::MissingNamedKey);
+    let amount_to_raise: U512 = storage::read(amount_to_raise_uref)
+        .unwrap_or_revert()
+        .unwrap_or_revert();
+    if u256_to_u512(balance) >= amount_to_raise {
+        runtime::revert(FundRaisingError::GoalAlreadyMet)
+    }
+
+    // Set `finalized` before issuing any transfers so a malicious caller cannot invoke
+    // `refund_token` repeatedly and drain each donor's contribution more than once.
+    storage::write(finalized_uref, true);
+
+    let ledger_seed_uref = get_uref(LEDGER, FundRaisingError::MissingLedgerSeedURef);
+    let donor_accounts_uref = get_uref(DONOR_ACCOUNTS, FundRaisingError::MissingNamedKey);
+    let donor_accounts: Vec<AccountHash> = storage::read(donor_accounts_uref)
+        .unwrap_or_revert()
+        .unwrap_or_revert();
+
+    for donor in donor_accounts {
+        if let Some(record) =
+            storage::dictionary_get::<LedgerRecord>(ledger_seed_uref, &donor.to_string())
+                .unwrap_or_revert()
+        {
+            if !record.total_donated.is_zero() {
+                let amount = u512_to_u256(record.total_donated);
+                runtime::call_contract::<()>(
+                    token_contract_hash,
+                    TOKEN_TRANSFER_ENTRY_POINT,
+                    runtime_args! {
+                        TOKEN_RECIPIENT_ARG => Key::Account(donor),
+                        AMOUNT_ARG => amount,
+                    },
+                );
+                emit_event(FundRaisingEvent::Refund {
+                    donor: Key::Account(donor),
+                    amount: record.total_donated,
+                });
+            }
+        }
+    }
+}
+
+// This entry point brings an upgraded contract version up to date. It always re-points
+// this version's own `fundraiser_contract_hash` / `fundraiser_package_hash` named keys at
+// itself (see `register_self_contract_keys`), and additionally brings the `ledger`
+// dictionary onto the current record layout: earlier versions of this contract stored a
+// bare `u64` donation count per donor instead of a `LedgerRecord`; this walks the donor
+// list recorded in `donor_accounts` and rewrites any entry still in that older layout.
+// The ledger rewrite is guarded by `contract_version` so it only does work the first time
+// it runs after an upgrade that actually changed the record layout.
+#[no_mangle]
+pub extern "C" fn migrate() {
+    // This version's named keys were seeded from the previous version's
+    // `export_named_keys`, so `fundraiser_contract_hash` / `fundraiser_package_hash`
+    // still point at the superseded contract. Re-point them at this version on every
+    // upgrade, regardless of whether the ledger layout below also needs rewriting.
+    register_self_contract_keys();
+
+    let contract_version_uref = get_uref(CONTRACT_VERSION_KEY, FundRaisingError::MissingNamedKey);
+    let contract_version: u32 = storage::read(contract_version_uref)
+        .unwrap_or_revert()
+        .unwrap_or_revert();
+    if contract_version >= CURRENT_CONTRACT_VERSION {
+        return;
+    }
+
+    let ledger_seed_uref = get_uref(LEDGER, FundRaisingError::MissingLedgerSeedURef);
+    let donor_accounts_uref = get_uref(DONOR_ACCOUNTS, FundRaisingError::MissingNamedKey);
+    let donor_accounts: Vec<AccountHash> = storage::read(donor_accounts_uref)
+        .unwrap_or_revert()
+        .unwrap_or_revert();
+
+    for donor in donor_accounts {
+        let donor_key = donor.to_string();
+        let already_migrated =
+            storage::dictionary_get::<LedgerRecord>(ledger_seed_uref, &donor_key)
+                .unwrap_or_revert()
+                .is_some();
+        if already_migrated {
+            continue;
+        }
+
+        if let Some(donation_count) =
+            storage::dictionary_get::<u64>(ledger_seed_uref, &donor_key).unwrap_or_revert()
+        {
+            storage::dictionary_put(
+                ledger_seed_uref,
+                &donor_key,
+                LedgerRecord {
+                    donation_count,
+                    total_donated: U512::zero(),
+                    last_block_time: runtime::get_blocktime(),
+                },
+            );
+        }
+    }
+
+    storage::write(contract_version_uref, CURRENT_CONTRACT_VERSION);
+}
+
+// This entry point hands off the admin role to a new account. Only the current admin
+// may call it.
+#[no_mangle]
+pub extern "C" fn set_admin() {
+    require_admin();
+    let new_admin: Key = runtime::get_named_arg(ADMIN_ARG);
+    let admin_uref = get_uref(ADMIN_KEY, FundRaisingError::MissingNamedKey);
+    storage::write(admin_uref, new_admin);
+}
+
+// This entry point blocks an account from donating. Only the admin may call it. Storing
+// blacklisted accounts in a dictionary, rather than a growable `Vec` under a single
+// named key, keeps the gas cost of each addition constant no matter how large the
+// blacklist grows.
+#[no_mangle]
+pub extern "C" fn add_to_blacklist() {
+    require_admin();
+    let account: Key = runtime::get_named_arg(ACCOUNT_ARG);
+    let account_hash = if let Key::Account(account_hash) = account {
+        account_hash
+    } else {
+        runtime::revert(FundRaisingError::InvalidKeyVariant)
+    };
+
+    let blacklist_seed_uref = get_uref(BLACKLIST, FundRaisingError::MissingNamedKey);
+    storage::dictionary_put(blacklist_seed_uref, &account_hash.to_string(), true);
+}
+
+// This entry point lifts a previously imposed block on an account. Only the admin may
+// call it.
+#[no_mangle]
+pub extern "C" fn remove_from_blacklist() {
+    require_admin();
+    let account: Key = runtime::get_named_arg(ACCOUNT_ARG);
+    let account_hash = if let Key::Account(account_hash) = account {
+        account_hash
+    } else {
+        runtime::revert(FundRaisingError::InvalidKeyVariant)
+    };
+
+    let blacklist_seed_uref = get_uref(BLACKLIST, FundRaisingError::MissingNamedKey);
+    storage::dictionary_put(blacklist_seed_uref, &account_hash.to_string(), false);
+}
+
+// Reads and accumulates a donor's ledger record, recording the newly donated amount.
+// When this is the donor's first donation, their account hash is appended to the list
+// of donor accounts so that `refund` can later walk every donor.
+fn update_ledger_record(account_hash: AccountHash, amount: U512) -> LedgerRecord {
+    let ledger_seed_uref = get_uref(LEDGER, FundRaisingError::MissingLedgerSeedURef);
+
+    let mut record = read_ledger_record(account_hash).unwrap_or_else(|| {
+        let donor_accounts_uref = get_uref(DONOR_ACCOUNTS, FundRaisingError::MissingNamedKey);
+        let mut donor_accounts: Vec<AccountHash> = storage::read(donor_accounts_uref)
+            .unwrap_or_revert()
+            .unwrap_or_revert();
+        donor_accounts.push(account_hash);
+        storage::write(donor_accounts_uref, donor_accounts);
+
+        LedgerRecord {
+            donation_count: 0,
+            total_donated: U512::zero(),
+            last_block_time: runtime::get_blocktime(),
+        }
+    });
+
+    record.donation_count += 1;
+    record.total_donated += amount;
+    record.last_block_time = runtime::get_blocktime();
+
+    storage::dictionary_put(ledger_seed_uref, &account_hash.to_string(), record);
+    record
+}
+
+// The CLType of the `(donation_count, total_donated, last_block_time, contract_hash)`
+// receipt tuple returned by `donate` and `get_receipt`.
+fn receipt_cl_type() -> CLType {
+    CLType::Tuple4(Box::new([
+        CLType::U64,
+        CLType::U512,
+        CLType::U64,
+        CLType::ByteArray(32),
+    ]))
+}
+
+// Builds a donor's receipt: their donation count, total donated, and the block time of
+// their most recent donation, alongside this contract's own hash so a calling contract
+// can verify which fundraiser the receipt came from. Reads the `fundraiser_contract_hash`
+// named key `init` registered on this contract's own named keys.
+fn build_receipt(record: LedgerRecord) -> (u64, U512, BlockTime, ContractHash) {
+    let contract_hash_key = runtime::get_key(CONTRACT_HASH_KEY)
+        .unwrap_or_revert_with(FundRaisingError::MissingNamedKey);
+    let contract_hash = ContractHash::new(contract_hash_key.into_hash().unwrap_or_revert());
+
+    (
+        record.donation_count,
+        record.total_donated,
+        record.last_block_time,
+        contract_hash,
+    )
+}
+
+// Registers this contract's own hash and package hash as named keys on itself, read off
+// the current frame of the call stack. A `Contract`'s named keys are scoped to that
+// `Contract` alone, so the copies `call()` puts on the installing account (to let that
+// account look them up afterwards) are never visible from inside this contract's own
+// execution. `this_contract_key` and `build_receipt` need to resolve these from within
+// entry points that run in this contract's context. `init` calls this on first install;
+// `migrate` calls it again on every upgrade, since a freshly published version's named
+// keys are seeded from the *previous* version's `export_named_keys` and would otherwise
+// still point at the old, superseded contract hash.
+fn register_self_contract_keys() {
+    let (contract_package_hash, contract_hash) = match runtime::get_call_stack().last() {
+        Some(CallStackElement::StoredContract {
+            contract_package_hash,
+            contract_hash,
+        }) => (*contract_package_hash, *contract_hash),
+        _ => runtime::revert(FundRaisingError::InvalidKeyVariant),
+    };
+    runtime::put_key(CONTRACT_HASH_KEY, contract_hash.into());
+    runtime::put_key(FUNDRAISER_PACKAGE_HASH_KEY, contract_package_hash.into());
+}
+
+// Reads a donor's ledger record, if one exists yet.
+fn read_ledger_record(account_hash: AccountHash) -> Option<LedgerRecord> {
+    let ledger_seed_uref = get_uref(LEDGER, FundRaisingError::MissingLedgerSeedURef);
+    storage::dictionary_get::<LedgerRecord>(ledger_seed_uref, &account_hash.to_string())
+        .unwrap_or_revert()
+}
+
+// Reads the CEP-18 token contract this fundraiser accepts, or `None` if it was set up
+// for native CSPR donations instead.
+fn get_accepted_token() -> Option<ContractHash> {
+    let accepted_token_uref = get_uref(ACCEPTED_TOKEN_KEY, FundRaisingError::MissingNamedKey);
+    storage::read(accepted_token_uref)
+        .unwrap_or_revert()
+        .unwrap_or_revert()
+}
+
+// Reverts with `WrongDonationMode` unless this fundraiser's accepted token matches
+// `expected` — `None` for native CSPR, `Some(hash)` for that CEP-18 token.
+fn assert_accepted_token(expected: Option<ContractHash>) {
+    if get_accepted_token() != expected {
+        runtime::revert(FundRaisingError::WrongDonationMode)
+    }
+}
+
+// Converts a CEP-18 `U256` token amount into the `U512` the ledger and event log use,
+// so native and token donations share one accounting representation.
+fn u256_to_u512(amount: U256) -> U512 {
+    let mut bytes = [0u8; 32];
+    amount.to_little_endian(&mut bytes);
+    U512::from_little_endian(&bytes)
+}
+
+// Converts a `U512` ledger amount back into the `U256` a CEP-18 token transfer expects.
+// Only ever used on amounts that originated from `donate_token`'s own `U256` argument (via
+// `u256_to_u512`), so the value always fits back into a `U256`.
+fn u512_to_u256(amount: U512) -> U256 {
+    let mut bytes = [0u8; 64];
+    amount.to_little_endian(&mut bytes);
+    U256::from_little_endian(&bytes[..32])
+}
+
+// Derives this contract's own `Key`, as seen by the cross-called CEP-18 token contract,
+// from the `fundraiser_package_hash` named key `init` registers on this contract's own
+// named keys (distinct from the copy `call()` leaves on the installing account).
+fn this_contract_key() -> Key {
+    let package_hash_key = runtime::get_key(FUNDRAISER_PACKAGE_HASH_KEY)
+        .unwrap_or_revert_with(FundRaisingError::MissingNamedKey);
+    Key::Hash(package_hash_key.into_hash().unwrap_or_revert())
+}
+
+// Reverts with `Unauthorized` unless the originating caller (`runtime::get_caller()`,
+// i.e. the account that signed the deploy) is the stored admin account. This does not
+// delegate to an intermediate calling contract — only the signing account itself can
+// hold the admin role.
+fn require_admin() {
+    let admin_uref = get_uref(ADMIN_KEY, FundRaisingError::MissingNamedKey);
+    let admin: Key = storage::read(admin_uref).unwrap_or_revert().unwrap_or_revert();
+    if admin != Key::Account(runtime::get_caller()) {
+        runtime::revert(FundRaisingError::Unauthorized)
+    }
+}
+
+// Reverts with `GoalAlreadyMet` if the fundraiser has already been finalized or
+// refunded, and with `DeadlinePassed` once its deadline has elapsed. Called by `donate`
+// and `donate_token` so that a concluded or expired fundraiser can no longer accept
+// donations — once `finalize`/`refund` has run, the purse can never be emptied again, and
+// accepting contributions after the deadline would let `donate` push a late fundraiser
+// over the goal that `refund` is supposed to be evaluating it against.
+fn assert_fundraiser_open() {
+    let finalized_uref = get_uref(FINALIZED_KEY, FundRaisingError::MissingNamedKey);
+    let finalized: bool = storage::read(finalized_uref).unwrap_or_revert().unwrap_or_revert();
+    if finalized {
+        runtime::revert(FundRaisingError::GoalAlreadyMet)
+    }
+    if deadline_has_passed() {
+        runtime::revert(FundRaisingError::DeadlinePassed)
+    }
+}
+
+// Reverts with `BlacklistedAccount` if `account_hash` is on the donor blacklist.
+fn reject_if_blacklisted(account_hash: AccountHash) {
+    let blacklist_seed_uref = get_uref(BLACKLIST, FundRaisingError::MissingNamedKey);
+    let is_blacklisted = storage::dictionary_get::<bool>(blacklist_seed_uref, &account_hash.to_string())
+        .unwrap_or_revert()
+        .unwrap_or(false);
+    if is_blacklisted {
+        runtime::revert(FundRaisingError::BlacklistedAccount)
+    }
+}
+
+// Looks up a named key and unwraps it to the `URef` backing it, reverting with `error`
+// if the named key is missing.
+fn get_uref(name: &str, error: FundRaisingError) -> URef {
+    *runtime::get_key(name)
+        .unwrap_or_revert_with(error)
+        .as_uref()
+        .unwrap_or_revert()
+}
+
+// Returns whether the fundraiser's deadline (`time_started + duration`) has elapsed.
+fn deadline_has_passed() -> bool {
+    let time_started_uref = get_uref(TIME_STARTED_KEY, FundRaisingError::MissingNamedKey);
+    let time_started: BlockTime = storage::read(time_started_uref)
+        .unwrap_or_revert()
+        .unwrap_or_revert();
+
+    let duration_uref = get_uref(DURATION_KEY, FundRaisingError::MissingNamedKey);
+    let duration: u64 = storage::read(duration_uref).unwrap_or_revert().unwrap_or_revert();
+
+    let deadline = u64::from(time_started) + duration;
+    u64::from(runtime::get_blocktime()) > deadline
 }
 
 //This is the full `call` function as defined within the donation contract.
@@ -106,7 +1045,15 @@ pub extern "C" fn call() {
     // This establishes the `init` entry point for initializing the contract's infrastructure.
     let init_entry_point = EntryPoint::new(
         ENTRY_POINT_INIT,
-        vec![],
+        vec![
+            Parameter::new(AMOUNT_TO_RAISE_ARG, CLType::U512),
+            Parameter::new(DURATION_ARG, CLType::U64),
+            Parameter::new(MAKER_ARG, CLType::Key),
+            Parameter::new(
+                TOKEN_CONTRACT_HASH_ARG,
+                CLType::Option(Box::new(CLType::ByteArray(32))),
+            ),
+        ],
         CLType::Unit,
         EntryPointAccess::Public,
         EntryPointType::Contract,
@@ -115,8 +1062,23 @@ pub extern "C" fn call() {
     // This establishes the `donate` entry point for callers looking to donate.
     let donate_entry_point = EntryPoint::new(
         ENTRY_POINT_DONATE,
+        vec![
+            Parameter::new(DONATING_ACCOUNT_KEY, CLType::Key),
+            Parameter::new(DONATION_PURSE_ARG, CLType::URef),
+            Parameter::new(AMOUNT_ARG, CLType::U512),
+        ],
+        receipt_cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    // This establishes the `get_receipt` entry point, which returns a donor's
+    // verifiable receipt: `(donation_count, total_donated, last_block_time,
+    // contract_hash)`.
+    let get_receipt_entry_point = EntryPoint::new(
+        ENTRY_POINT_GET_RECEIPT,
         vec![Parameter::new(DONATING_ACCOUNT_KEY, CLType::Key)],
-        CLType::URef,
+        receipt_cl_type(),
         EntryPointAccess::Public,
         EntryPointType::Contract,
     );
@@ -131,6 +1093,20 @@ pub extern "C" fn call() {
         EntryPointType::Contract,
     );
 
+    // This establishes the `export_named_keys` entry point, which the upgrade branch
+    // below calls on the previous contract version to carry its named keys forward onto
+    // the new one.
+    let export_named_keys_entry_point = EntryPoint::new(
+        ENTRY_POINT_EXPORT_NAMED_KEYS,
+        vec![],
+        CLType::Map {
+            key: Box::new(CLType::String),
+            value: Box::new(CLType::Key),
+        },
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
     // This establishes an entry point called `funds_raised` that returns the total amount
     // donated by all participants.
     let funds_raised_entry_point = EntryPoint::new(
@@ -140,33 +1116,295 @@ pub extern "C" fn call() {
         EntryPointAccess::Public,
         EntryPointType::Contract,
     );
+
+    // This establishes the `donate_token` entry point, the CEP-18 counterpart to
+    // `donate` for fundraisers accepting a specific token instead of native CSPR.
+    let donate_token_entry_point = EntryPoint::new(
+        ENTRY_POINT_DONATE_TOKEN,
+        vec![
+            Parameter::new(DONATING_ACCOUNT_KEY, CLType::Key),
+            Parameter::new(AMOUNT_ARG, CLType::U256),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    // This establishes an entry point called `get_token_funds_raised` that returns the
+    // total amount of the accepted CEP-18 token donated by all participants.
+    let get_token_funds_raised_entry_point = EntryPoint::new(
+        ENTRY_POINT_GET_TOKEN_FUNDS_RAISED,
+        vec![],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    // This establishes the `finalize` entry point, callable once the fundraising goal has
+    // been met, which pays the beneficiary out.
+    let finalize_entry_point = EntryPoint::new(
+        ENTRY_POINT_FINALIZE,
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    // This establishes the `refund` entry point, callable once the deadline has passed
+    // without the fundraising goal having been met, which returns every donor's
+    // contribution.
+    let refund_entry_point = EntryPoint::new(
+        ENTRY_POINT_REFUND,
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    // This establishes the `finalize_token` entry point, the CEP-18 counterpart to
+    // `finalize` for fundraisers accepting a token instead of native CSPR.
+    let finalize_token_entry_point = EntryPoint::new(
+        ENTRY_POINT_FINALIZE_TOKEN,
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    // This establishes the `refund_token` entry point, the CEP-18 counterpart to
+    // `refund` for fundraisers accepting a token instead of native CSPR.
+    let refund_token_entry_point = EntryPoint::new(
+        ENTRY_POINT_REFUND_TOKEN,
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    // This establishes the `migrate` entry point, which rewrites any ledger entries left
+    // over from an older contract layout after an upgrade.
+    let migrate_entry_point = EntryPoint::new(
+        ENTRY_POINT_MIGRATE,
+        vec![],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    // This establishes the `set_admin` entry point, which hands off the admin role.
+    let set_admin_entry_point = EntryPoint::new(
+        ENTRY_POINT_SET_ADMIN,
+        vec![Parameter::new(ADMIN_ARG, CLType::Key)],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    // This establishes the `add_to_blacklist` entry point, which blocks an account from
+    // donating.
+    let add_to_blacklist_entry_point = EntryPoint::new(
+        ENTRY_POINT_ADD_TO_BLACKLIST,
+        vec![Parameter::new(ACCOUNT_ARG, CLType::Key)],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    // This establishes the `remove_from_blacklist` entry point, which lifts a
+    // previously imposed block on an account.
+    let remove_from_blacklist_entry_point = EntryPoint::new(
+        ENTRY_POINT_REMOVE_FROM_BLACKLIST,
+        vec![Parameter::new(ACCOUNT_ARG, CLType::Key)],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(init_entry_point);
+    entry_points.add_entry_point(donate_entry_point);
+    entry_points.add_entry_point(get_receipt_entry_point);
+    entry_points.add_entry_point(export_named_keys_entry_point);
+    entry_points.add_entry_point(donate_token_entry_point);
+    entry_points.add_entry_point(get_token_funds_raised_entry_point);
+    entry_points.add_entry_point(get_donation_count_entry_point);
+    entry_points.add_entry_point(funds_raised_entry_point);
+    entry_points.add_entry_point(finalize_entry_point);
+    entry_points.add_entry_point(refund_entry_point);
+    entry_points.add_entry_point(finalize_token_entry_point);
+    entry_points.add_entry_point(refund_token_entry_point);
+    entry_points.add_entry_point(migrate_entry_point);
+    entry_points.add_entry_point(set_admin_entry_point);
+    entry_points.add_entry_point(add_to_blacklist_entry_point);
+    entry_points.add_entry_point(remove_from_blacklist_entry_point);
+
+    match runtime::get_key(FUNDRAISER_PACKAGE_HASH_KEY) {
+        Some(package_hash_key) => {
+            // Upgrading an existing fundraiser: publish a new version against the same
+            // package instead of creating a fresh one. Each contract version under a
+            // package has its own, independent named keys — Casper does NOT carry the
+            // previous version's named keys forward on its own — so the previous
+            // version's named keys (fundraising purse, ledger, donor list, admin,
+            // blacklist, event log, …) have to be fetched explicitly and passed into
+            // the new version, or they would be orphaned.
+            let package_hash = package_hash_key.into_hash().unwrap_or_revert().into();
+
+            let previous_contract_hash: ContractHash = runtime::get_key(CONTRACT_HASH_KEY)
+                .unwrap_or_revert_with(FundRaisingError::MissingNamedKey)
+                .into_hash()
+                .unwrap_or_revert()
+                .into();
+
+            // A fundraiser still running code from before `export_named_keys` existed
+            // (chunk0-3) has no entry point that can report its own named keys back to
+            // us — cross-calling one that isn't there would simply revert the whole
+            // upgrade deploy. For that population, the caller must instead read the
+            // previous version's named keys off-chain (a global state query against
+            // `previous_contract_hash` needs no contract cooperation) and pass them in
+            // directly as `previous_named_keys`. Anything from chunk0-3 onward can pass
+            // `None` for it (the argument itself is always required) and let the
+            // cross-call fetch them automatically.
+            let previous_named_keys_arg: Option<NamedKeys> =
+                runtime::get_named_arg(PREVIOUS_NAMED_KEYS_ARG);
+            let previous_named_keys = match previous_named_keys_arg {
+                Some(named_keys) => named_keys,
+                None => runtime::call_contract(
+                    previous_contract_hash,
+                    ENTRY_POINT_EXPORT_NAMED_KEYS,
+                    runtime_args! {},
+                ),
+            };
+
+            let (contract_hash, _contract_version) =
+                storage::add_contract_version(package_hash, entry_points, previous_named_keys);
+            runtime::put_key(CONTRACT_HASH_KEY, contract_hash.into());
+
+            // Bring the ledger up to the current record layout if it was left behind by
+            // an older version of this contract.
+            runtime::call_contract::<()>(contract_hash, ENTRY_POINT_MIGRATE, runtime_args! {});
+        }
+        None => {
+            let lock_package: bool = runtime::get_named_arg(LOCK_PACKAGE_ARG);
+
+            // Register the CES-style event schema and the event-count uref as named keys
+            // at contract creation time, so off-chain tooling can discover the event
+            // types and replay the full history from `__events_schema` /
+            // `__events_length` without first having to call into the contract.
+            let mut named_keys = NamedKeys::new();
+            named_keys.insert(
+                EVENTS_SCHEMA_KEY.to_string(),
+                storage::new_uref(event_schemas()).into(),
+            );
+            named_keys.insert(
+                EVENTS_LENGTH_KEY.to_string(),
+                storage::new_uref(0u64).into(),
+            );
+
+            let (contract_hash, _contract_version) = if lock_package {
+                // A locked package can never receive another version: no future
+                // `upgrade` is possible once installed this way.
+                storage::new_locked_contract(
+                    entry_points,
+                    Some(named_keys),
+                    Some(FUNDRAISER_PACKAGE_HASH_KEY.to_string()),
+                    Some(FUNDRAISER_ACCESS_UREF_KEY.to_string()),
+                )
+            } else {
+                storage::new_contract(
+                    entry_points,
+                    Some(named_keys),
+                    Some(FUNDRAISER_PACKAGE_HASH_KEY.to_string()),
+                    Some(FUNDRAISER_ACCESS_UREF_KEY.to_string()),
+                )
+            };
+
+            runtime::put_key(CONTRACT_HASH_KEY, contract_hash.into());
+
+            let amount_to_raise: U512 = runtime::get_named_arg(AMOUNT_TO_RAISE_ARG);
+            let duration: u64 = runtime::get_named_arg(DURATION_ARG);
+            let maker: Key = runtime::get_named_arg(MAKER_ARG);
+            let token_contract_hash: Option<ContractHash> =
+                runtime::get_named_arg(TOKEN_CONTRACT_HASH_ARG);
+
+            // Call the init entry point to setup and create the fundraising purse,
+            // record the fundraising goal/deadline/beneficiary, and create the ledger
+            // to track donations made.
+            runtime::call_contract::<()>(
+                contract_hash,
+                ENTRY_POINT_INIT,
+                runtime_args! {
+                    AMOUNT_TO_RAISE_ARG => amount_to_raise,
+                    DURATION_ARG => duration,
+                    MAKER_ARG => maker,
+                    TOKEN_CONTRACT_HASH_ARG => token_contract_hash,
+                },
+            )
+        }
+    }
 }
 
-let mut entry_points = EntryPoints::new();
-entry_points.add_entry_point(init_entry_point);
-entry_points.add_entry_point(donate_entry_point);
-entry_points.add_entry_point(get_donation_count_entry_point);
-entry_points.add_entry_point(funds_raised_entry_point);
+// Unit tests covering the pure, host-independent pieces of this contract: the manual
+// `ToBytes`/`FromBytes` round trips for the custom types written to global state, and the
+// `U256`/`U512` conversion used to share one accounting representation between native and
+// token donations. `finalize`/`refund`/`migrate` and the rest of the entry points are not
+// covered here — exercising those means driving purse transfers, cross-contract calls,
+// and contract versioning through a real execution environment (e.g.
+// `casper-engine-test-support`), which this single-file snapshot has no workspace or test
+// harness dependency for.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ledger_record_bytes_round_trip() {
+        let record = LedgerRecord {
+            donation_count: 3,
+            total_donated: U512::from(12_345u64),
+            last_block_time: BlockTime::new(678),
+        };
 
+        let bytes = record.to_bytes().unwrap();
+        assert_eq!(bytes.len(), record.serialized_length());
 
-let (contract_hash, _contract_version) = storage::new_contract(
-    entry_points,
-    None,
-    Some("fundraiser_package_hash".to_string()),
-    Some("fundraiser_access_uref".to_string()),
-);
+        let (deserialized, remainder) = LedgerRecord::from_bytes(&bytes).unwrap();
+        assert!(remainder.is_empty());
+        assert_eq!(deserialized.donation_count, record.donation_count);
+        assert_eq!(deserialized.total_donated, record.total_donated);
+        assert_eq!(deserialized.last_block_time, record.last_block_time);
+    }
 
-runtime::put_key("fundraiser_contract_hash", contract_hash.into());
-// Call the init entry point to setup and create the fundraising purse
-// and the ledger to track donations made.
-runtime::call_contract::<()>(contract_hash, ENTRY_POINT_INIT, runtime_args! {})
+    #[test]
+    fn fund_raising_event_bytes_round_trip() {
+        let events = vec![
+            FundRaisingEvent::Donation {
+                donor: Key::Account(AccountHash::new([1u8; 32])),
+                amount: U512::from(100u64),
+                new_total: U512::from(250u64),
+            },
+            FundRaisingEvent::GoalReached {
+                total: U512::from(1_000u64),
+            },
+            FundRaisingEvent::Refund {
+                donor: Key::Account(AccountHash::new([2u8; 32])),
+                amount: U512::from(50u64),
+            },
+        ];
 
+        for event in events {
+            let bytes = event.to_bytes().unwrap();
+            assert_eq!(bytes.len(), event.serialized_length());
 
-pub fn new_locked_contract(
-    entry_points: EntryPoints,
-    named_keys: Option<NamedKeys>,
-    hash_name: Option<String>,
-    uref_name: Option<String>,
-) -> (ContractHash, ContractVersion) {
-    create_contract(entry_points, named_keys, hash_name, uref_name, true)
+            let (deserialized, remainder) = FundRaisingEvent::from_bytes(&bytes).unwrap();
+            assert!(remainder.is_empty());
+            assert_eq!(deserialized.to_bytes().unwrap(), event.to_bytes().unwrap());
+        }
+    }
+
+    #[test]
+    fn u256_u512_conversion_round_trips() {
+        let amount = U256::from(98_765_432u64);
+        let as_u512 = u256_to_u512(amount);
+        assert_eq!(as_u512, U512::from(98_765_432u64));
+        assert_eq!(u512_to_u256(as_u512), amount);
+    }
 }